@@ -0,0 +1,130 @@
+use serde::ser;
+use std::fmt::{self, Display};
+
+/// Error type produced by the serializers in [`crate::ser`].
+///
+/// Besides a plain [`Error::Custom`] message, this carries a breadcrumb trail of the struct
+/// fields and sequence indices an error passed through on its way up, so a failure deep inside a
+/// GD level/object string can be traced back to the specific numbered field that caused it. Use
+/// [`Error::field`] and [`Error::index`] to attach a breadcrumb to an error as it bubbles up.
+#[derive(Debug)]
+pub enum Error {
+    /// A custom error message, as produced via `serde::ser::Error::custom`.
+    Custom(String),
+
+    /// A `Serializer`/`SerializeXXX` operation that isn't supported by the serializer it was
+    /// called on, such as `serialize_map` on [`crate::ser::indexed::IndexedSerializer`].
+    Unsupported(&'static str),
+
+    /// `cause` occurred while serializing the struct field named by the first element.
+    Field(&'static str, Box<Error>),
+
+    /// `cause` occurred while serializing the sequence element at the given index.
+    Index(usize, Box<Error>),
+}
+
+impl Error {
+    /// Wraps this error with the struct field it occurred under, for use in [`Display`].
+    pub fn field(self, field: &'static str) -> Self {
+        Error::Field(field, Box::new(self))
+    }
+
+    /// Wraps this error with the sequence index it occurred under, for use in [`Display`].
+    pub fn index(self, index: usize) -> Self {
+        Error::Index(index, Box::new(self))
+    }
+
+    /// Renders this error as part of a chain started by a containing [`Error::Field`] or
+    /// [`Error::Index`]: breadcrumbs are joined with `->`, and the final message is separated
+    /// from the last breadcrumb with `:`.
+    fn fmt_chained(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Field(..) | Error::Index(..) => write!(f, " -> {}", self),
+            leaf => write!(f, ": {}", leaf),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Custom(message) => write!(f, "{}", message),
+            Error::Unsupported(what) => write!(f, "{} is not supported by this serializer", what),
+            Error::Field(field, cause) => {
+                write!(f, "field {:?}", field)?;
+                cause.fmt_chained(f)
+            },
+            Error::Index(index, cause) => {
+                write!(f, "index {}", index)?;
+                cause.fmt_chained(f)
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ser::indexed::{to_string, BytesEncoding};
+    use serde::{
+        ser::{SerializeSeq, SerializeStruct},
+        Serialize, Serializer,
+    };
+
+    #[test]
+    fn display_chains_field_and_index_breadcrumbs() {
+        let error = Error::Custom("invalid utf8".to_string()).index(2).field("35");
+
+        assert_eq!(error.to_string(), "field \"35\" -> index 2: invalid utf8");
+    }
+
+    /// A value whose `Serialize` impl always fails, so tests can force an error at a specific
+    /// struct field or sequence index.
+    struct FailsToSerialize;
+
+    impl Serialize for FailsToSerialize {
+        fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(ser::Error::custom("invalid utf8"))
+        }
+    }
+
+    /// A 3-element sequence whose last element fails to serialize.
+    struct ThirdElementFails;
+
+    impl Serialize for ThirdElementFails {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(3))?;
+            seq.serialize_element(&1i32)?;
+            seq.serialize_element(&2i32)?;
+            seq.serialize_element(&FailsToSerialize)?;
+            seq.end()
+        }
+    }
+
+    /// A single-field struct whose field is [`ThirdElementFails`], named the way the request's
+    /// own breadcrumb example (`field "35" -> index 2: ...`) names it.
+    struct StructWithFailingField;
+
+    impl Serialize for StructWithFailingField {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("StructWithFailingField", 1)?;
+            s.serialize_field("35", &ThirdElementFails)?;
+            s.end()
+        }
+    }
+
+    #[test]
+    fn field_and_index_breadcrumbs_trace_a_real_serialization_failure() {
+        let error = to_string(&StructWithFailingField, ":", true, BytesEncoding::Base64UrlSafe).unwrap_err();
+
+        assert_eq!(error.to_string(), "field \"35\" -> index 2: invalid utf8");
+    }
+}
@@ -2,46 +2,247 @@ use crate::ser::error::Error;
 use dtoa::Floating;
 use itoa::Integer;
 use serde::{
-    ser::{Error as _, Impossible, SerializeStruct},
+    ser::{Error as _, Impossible, SerializeSeq, SerializeStruct, SerializeTuple},
     Serialize, Serializer,
 };
-use std::fmt::Display;
+use std::{fmt::Display, io, mem};
+
+/// Delimiters used for nested sequences, in order of nesting depth.
+///
+/// GD's format nests delimiters: the outer record is `:`-delimited, but fields that are
+/// themselves lists (level object strings, color channels, HSV values, ...) are `|`-, `,`- or
+/// `_`-delimited, in that order as nesting gets deeper. The innermost delimiter is reused for
+/// any nesting beyond this.
+const NESTED_DELIMITERS: [&str; 3] = ["|", ",", "_"];
+
+/// Selects how [`Serializer::serialize_bytes`] encodes byte fields.
+///
+/// Different GD endpoints expect different wire representations for binary data: passwords and
+/// the account GJP are XORed with a fixed key before base64 encoding, while other fields just
+/// expect plain base64 in one alphabet or another.
+#[derive(Debug, Clone, Copy)]
+pub enum BytesEncoding {
+    /// Standard base64 alphabet (`+`, `/`), with padding.
+    Base64Standard,
+
+    /// URL-safe base64 alphabet (`-`, `_`), with padding.
+    Base64UrlSafe,
+
+    /// URL-safe base64 alphabet (`-`, `_`), without padding.
+    Base64UrlSafeNoPad,
+
+    /// RobTop's reversible "cipher": XOR the plaintext with `key` (cycling the key byte-by-byte
+    /// over the data), then base64-encode the result using the URL-safe alphabet.
+    XorBase64 { key: &'static [u8] },
+}
+
+impl BytesEncoding {
+    fn base64_config(self) -> base64::Config {
+        match self {
+            BytesEncoding::Base64Standard => base64::STANDARD,
+            BytesEncoding::Base64UrlSafe | BytesEncoding::XorBase64 { .. } => base64::URL_SAFE,
+            BytesEncoding::Base64UrlSafeNoPad => base64::URL_SAFE_NO_PAD,
+        }
+    }
+}
+
+/// Selects how [`Serializer::serialize_bool`] renders a `bool`.
+#[derive(Debug, Clone, Copy)]
+pub enum BoolRepresentation {
+    /// `1` for `true`, `0` for `false`.
+    OneZero,
+
+    /// `1` for `true`, empty string for `false`.
+    EmptyVsPresent,
+}
+
+/// Abstraction over the byte sink an [`IndexedSerializer`] writes into.
+///
+/// This exists so the serializer can be generic over "buffer we can hand back as a `String`"
+/// (`Vec<u8>`) and "arbitrary `io::Write` destination" (`WriteSink<W>`) without duplicating all of
+/// the `Serializer`/`SerializeStruct` machinery for both cases.
+trait Sink: io::Write {
+    /// Base64-encodes `data` using `config` and writes the result to this sink.
+    ///
+    /// The default implementation goes through a temporary buffer. [`Vec<u8>`] overrides this to
+    /// encode directly into its own spare capacity, avoiding the extra allocation.
+    fn write_base64(&mut self, data: &[u8], config: base64::Config) -> Result<(), Error> {
+        let mut encoded = vec![0; data.len() * 4 / 3 + 4];
+        let written = base64::encode_config_slice(data, config, &mut encoded);
+        encoded.truncate(written);
+        self.write_all(&encoded).map_err(Error::custom)
+    }
+}
 
+impl Sink for Vec<u8> {
+    fn write_base64(&mut self, data: &[u8], config: base64::Config) -> Result<(), Error> {
+        // We need to use resize instead of reserve because the base64 method for encoding takes initialized
+        // slices
+        let idx = self.len();
+        self.resize(idx + data.len() * 4 / 3 + 4, 0);
+        // This won't panic because we just allocated the right amount of data to store this
+        let written = base64::encode_config_slice(data, config, &mut self[idx..]);
+        // Shorten our vec down to just what was written
+        self.resize(idx + written, 0);
+        Ok(())
+    }
+}
+
+/// Adapts an arbitrary [`io::Write`] into a [`Sink`], so it can back an [`IndexedSerializer`].
+///
+/// See [`IndexedWriteSerializer`] and [`to_writer`].
 #[allow(missing_debug_implementations)]
-pub struct IndexedSerializer {
-    delimiter: &'static [u8],
-    buffer: Vec<u8>,
+pub struct WriteSink<W: io::Write>(W);
+
+impl<W: io::Write> io::Write for WriteSink<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl<W: io::Write> Sink for WriteSink<W> {}
+
+#[allow(missing_debug_implementations)]
+// `Sink` is intentionally private: it's an internal seam between the `Vec<u8>`- and
+// `WriteSink<W>`-backed serializers, not something downstream crates are meant to implement.
+#[allow(private_bounds)]
+pub struct IndexedSerializer<S: Sink = Vec<u8>> {
+    /// Stack of delimiters, one per level of sequence nesting currently being serialized. The top
+    /// level (index 0) always holds the delimiter configured via [`SerializerConfig`]. Entering a
+    /// nested sequence pushes the next entry of [`NESTED_DELIMITERS`]; leaving it pops back to the
+    /// parent delimiter.
+    delimiters: Vec<&'static [u8]>,
+    buffer: S,
     map_like: bool,
+    encoding: BytesEncoding,
+    bool_representation: BoolRepresentation,
 
-    /// Value indicating whether this serializer has already serialized something. This is used to
-    /// check if we need to prepend the delimiter to the next field.
+    /// Whether a trailing delimiter is emitted after the last field of a struct. This applies to
+    /// every struct, including ones nested inside a sequence (which then get the sequence's
+    /// delimiter), but never to the end of a sequence.
+    trailing_delimiter: bool,
+
+    /// Per-nesting-level value indicating whether this serializer has already serialized something
+    /// at that level. This is used to check if we need to prepend the delimiter to the next field
+    /// or element.
     ///
-    /// Note that this field cannot simply be replaced in favor of a `buffer.len() == 0` check. In
-    /// case of list-like serialization the first field could be `None`, which is serialized to the
-    /// empty string. In that case, a delimiter needs to be appended, but since the buffer would
-    /// still be empty, no delimiter would be added.
-    is_start: bool,
+    /// Note that this cannot simply be replaced in favor of a `buffer.len() == 0` check. In case of
+    /// list-like serialization the first field could be `None`, which is serialized to the empty
+    /// string. In that case, a delimiter needs to be appended, but since the buffer would still be
+    /// empty, no delimiter would be added. This is also why this is tracked as a stack separate
+    /// from the delimiter stack: popping back out of a nested sequence must not disturb the start
+    /// state of the level it returns to.
+    starts: Vec<bool>,
+
+    /// Stack of "next element index" counters, one per level of sequence nesting, used to attach
+    /// an [`Error::index`] breadcrumb to errors produced while serializing a sequence element.
+    indices: Vec<usize>,
 }
 
-impl IndexedSerializer {
-    pub fn new(delimiter: &'static str, map_like: bool) -> Self {
+/// An [`IndexedSerializer`] that writes directly into an [`io::Write`] sink instead of buffering
+/// the entire output in memory. Useful for serializing straight into, e.g., a gzip encoder or a
+/// socket.
+pub type IndexedWriteSerializer<W> = IndexedSerializer<WriteSink<W>>;
+
+/// Builder for an [`IndexedSerializer`].
+///
+/// RobTop's various response formats are all variations on the same "delimited, indexed fields"
+/// theme, but disagree on the small details: the delimiter, whether fields are `key:value` or
+/// bare positional lists, how booleans are rendered, whether the record ends with a trailing
+/// delimiter, and how byte fields are encoded (see [`BytesEncoding`]). Configure all of those here
+/// and call [`build`](SerializerConfig::build) (or
+/// [`build_writer`](SerializerConfig::build_writer)) to get a serializer.
+#[derive(Debug, Clone)]
+pub struct SerializerConfig {
+    delimiter: &'static str,
+    map_like: bool,
+    bool_representation: BoolRepresentation,
+    trailing_delimiter: bool,
+    encoding: BytesEncoding,
+    capacity: usize,
+}
+
+impl SerializerConfig {
+    /// Creates a new config using `delimiter` to separate fields, with map-like output, `1`/`0`
+    /// booleans, no trailing delimiter, URL-safe base64 byte fields, and no starting capacity.
+    pub fn new(delimiter: &'static str) -> Self {
+        SerializerConfig {
+            delimiter,
+            map_like: true,
+            bool_representation: BoolRepresentation::OneZero,
+            trailing_delimiter: false,
+            encoding: BytesEncoding::Base64UrlSafe,
+            capacity: 0,
+        }
+    }
+
+    /// Sets whether fields are serialized as `key:value` pairs (`true`) or as a bare positional
+    /// list of values (`false`).
+    pub fn map_like(mut self, map_like: bool) -> Self {
+        self.map_like = map_like;
+        self
+    }
+
+    /// Sets how `bool` values are rendered.
+    pub fn bool_representation(mut self, bool_representation: BoolRepresentation) -> Self {
+        self.bool_representation = bool_representation;
+        self
+    }
+
+    /// Sets whether a trailing delimiter is emitted after the last field of every struct,
+    /// using the delimiter of the nesting level the struct sits at.
+    pub fn trailing_delimiter(mut self, trailing_delimiter: bool) -> Self {
+        self.trailing_delimiter = trailing_delimiter;
+        self
+    }
+
+    /// Sets how byte fields (`serialize_bytes`) are encoded.
+    pub fn bytes_encoding(mut self, encoding: BytesEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets the starting capacity of the in-memory buffer used by [`build`](SerializerConfig::build).
+    /// Has no effect on [`build_writer`](SerializerConfig::build_writer).
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Builds an [`IndexedSerializer`] that buffers its output in memory.
+    pub fn build(self) -> IndexedSerializer<Vec<u8>> {
         IndexedSerializer {
-            delimiter: delimiter.as_bytes(),
-            buffer: Vec::new(),
-            map_like,
-            is_start: true,
+            delimiters: vec![self.delimiter.as_bytes()],
+            buffer: Vec::with_capacity(self.capacity),
+            map_like: self.map_like,
+            encoding: self.encoding,
+            bool_representation: self.bool_representation,
+            trailing_delimiter: self.trailing_delimiter,
+            starts: vec![true],
+            indices: vec![],
         }
     }
 
-    pub fn with_capacity(delimiter: &'static str, map_like: bool, capacity: usize) -> Self {
+    /// Builds an [`IndexedSerializer`] that writes directly into `writer`.
+    pub fn build_writer<W: io::Write>(self, writer: W) -> IndexedSerializer<WriteSink<W>> {
         IndexedSerializer {
-            delimiter: delimiter.as_bytes(),
-            buffer: Vec::with_capacity(capacity),
-            map_like,
-            is_start: true,
+            delimiters: vec![self.delimiter.as_bytes()],
+            buffer: WriteSink(writer),
+            map_like: self.map_like,
+            encoding: self.encoding,
+            bool_representation: self.bool_representation,
+            trailing_delimiter: self.trailing_delimiter,
+            starts: vec![true],
+            indices: vec![],
         }
     }
+}
 
+impl IndexedSerializer<Vec<u8>> {
     pub fn finish(self) -> String {
         debug_assert!(std::str::from_utf8(&self.buffer[..]).is_ok());
 
@@ -50,24 +251,74 @@ impl IndexedSerializer {
         unsafe { String::from_utf8_unchecked(self.buffer) }
     }
 
-    fn append_integer<I: Integer>(&mut self, int: I) -> Result<(), Error> {
-        if self.is_start {
-            self.is_start = false;
+    fn into_vec(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+// See the comment on `IndexedSerializer` above for why the private `Sink` bound here is fine.
+#[allow(private_bounds)]
+impl<S: Sink> IndexedSerializer<S> {
+    fn delimiter(&self) -> &'static [u8] {
+        self.delimiters.last().copied().unwrap()
+    }
+
+    /// Prepends the delimiter for the current nesting level, unless this is the first value
+    /// serialized at that level.
+    fn advance(&mut self) -> Result<(), Error> {
+        if *self.starts.last().unwrap() {
+            *self.starts.last_mut().unwrap() = false;
         } else {
-            self.buffer.extend_from_slice(self.delimiter);
+            self.buffer.write_all(self.delimiter()).map_err(Error::custom)?;
         }
 
+        Ok(())
+    }
+
+    fn enter_seq(&mut self) -> Result<(), Error> {
+        // A nested sequence is itself a value at the current level, so it needs the same
+        // "is this the first value here" check as any scalar append before a new level is pushed
+        // for its own elements.
+        self.advance()?;
+
+        let depth = self.delimiters.len() - 1;
+        let inner = NESTED_DELIMITERS[depth.min(NESTED_DELIMITERS.len() - 1)].as_bytes();
+
+        self.delimiters.push(inner);
+        self.starts.push(true);
+        self.indices.push(0);
+
+        Ok(())
+    }
+
+    fn exit_seq(&mut self) {
+        self.delimiters.pop();
+        self.starts.pop();
+        self.indices.pop();
+    }
+
+    /// Serializes `value` as the next sequence element, attaching its index to any error it
+    /// produces.
+    fn serialize_indexed_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize,
+    {
+        let index = *self.indices.last().unwrap();
+        *self.indices.last_mut().unwrap() += 1;
+
+        value.serialize(&mut *self).map_err(|error| error.index(index))
+    }
+
+    fn append_integer<I: Integer>(&mut self, int: I) -> Result<(), Error> {
+        self.advance()?;
+
         itoa::write(&mut self.buffer, int).map_err(Error::custom)?;
 
         Ok(())
     }
 
     fn append_float<F: Floating>(&mut self, float: F) -> Result<(), Error> {
-        if self.is_start {
-            self.is_start = false;
-        } else {
-            self.buffer.extend_from_slice(self.delimiter);
-        }
+        self.advance()?;
 
         dtoa::write(&mut self.buffer, float).map_err(Error::custom)?;
 
@@ -75,31 +326,28 @@ impl IndexedSerializer {
     }
 
     fn append(&mut self, s: &str) -> Result<(), Error> {
-        if self.is_start {
-            self.is_start = false;
-        } else {
-            self.buffer.extend_from_slice(self.delimiter);
-        }
+        self.advance()?;
 
-        self.buffer.extend_from_slice(s.as_bytes());
-
-        Ok(())
+        self.buffer.write_all(s.as_bytes()).map_err(Error::custom)
     }
 }
 
-impl<'a> Serializer for &'a mut IndexedSerializer {
+impl<'a, S: Sink> Serializer for &'a mut IndexedSerializer<S> {
     type Error = Error;
     type Ok = ();
     type SerializeMap = Impossible<(), Error>;
-    type SerializeSeq = Impossible<(), Error>;
+    type SerializeSeq = Self;
     type SerializeStruct = Self;
     type SerializeStructVariant = Impossible<(), Error>;
-    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTuple = Self;
     type SerializeTupleStruct = Impossible<(), Error>;
     type SerializeTupleVariant = Impossible<(), Error>;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-        self.append(if v { "1" } else { "0" })
+        match self.bool_representation {
+            BoolRepresentation::OneZero => self.append(if v { "1" } else { "0" }),
+            BoolRepresentation::EmptyVsPresent => self.append(if v { "1" } else { "" }),
+        }
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
@@ -145,7 +393,7 @@ impl<'a> Serializer for &'a mut IndexedSerializer {
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
         // We don't need allocations for appending a single char
         // A buffer of size 4 is always enough to encode a char
-        let mut buffer : [u8; 4]= [0; 4];
+        let mut buffer: [u8; 4] = [0; 4];
         self.append(v.encode_utf8(&mut buffer))
     }
 
@@ -153,23 +401,25 @@ impl<'a> Serializer for &'a mut IndexedSerializer {
         self.append(v)
     }
 
-    // Here we serialize bytes by base64 encoding them, so it's always valid in Geometry Dash's format
+    // Here we serialize bytes by base64 encoding them (in the configured alphabet, optionally
+    // after XORing with a fixed key first), so it's always valid in Geometry Dash's format
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        use base64::URL_SAFE;
-        // We need to use resize instead of reserve because the base64 method for encoding takes initialized
-        // slices
-        let idx = self.buffer.len();
-        self.buffer.resize(idx + v.len() * 4 / 3 + 4, 0);
-        // This won't panic because we just allocated the right amount of data to store this
-        let written = base64::encode_config_slice(v, URL_SAFE, &mut self.buffer[idx..]);
-        // Shorten our vec down to just what was written
-        self.buffer.resize(idx + written, 0);
-        Ok(())
+        match self.encoding {
+            BytesEncoding::XorBase64 { key } => {
+                if key.is_empty() {
+                    return Err(Error::custom("XorBase64 encoding requires a non-empty key"));
+                }
+
+                let xored: Vec<u8> = v.iter().enumerate().map(|(i, byte)| byte ^ key[i % key.len()]).collect();
+
+                self.buffer.write_base64(&xored, self.encoding.base64_config())
+            },
+            _ => self.buffer.write_base64(v, self.encoding.base64_config()),
+        }
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        self.buffer.extend_from_slice(self.delimiter);
-        Ok(())
+        self.buffer.write_all(self.delimiter()).map_err(Error::custom)
     }
 
     fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
@@ -208,11 +458,13 @@ impl<'a> Serializer for &'a mut IndexedSerializer {
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        Err(Error::Unsupported("serialize_seq"))
+        self.enter_seq()?;
+        Ok(self)
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        Err(Error::Unsupported("serialize_tuple"))
+        self.enter_seq()?;
+        Ok(self)
     }
 
     fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
@@ -248,7 +500,7 @@ impl<'a> Serializer for &'a mut IndexedSerializer {
     }
 }
 
-impl<'a> SerializeStruct for &'a mut IndexedSerializer {
+impl<'a, S: Sink> SerializeStruct for &'a mut IndexedSerializer<S> {
     type Error = Error;
     type Ok = ();
 
@@ -259,10 +511,229 @@ impl<'a> SerializeStruct for &'a mut IndexedSerializer {
         if self.map_like {
             self.append(key)?;
         }
-        value.serialize(&mut **self)
+        value.serialize(&mut **self).map_err(|error| error.field(key))
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        if self.trailing_delimiter {
+            self.buffer.write_all(self.delimiter()).map_err(Error::custom)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, S: Sink> SerializeSeq for &'a mut IndexedSerializer<S> {
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.serialize_indexed_element(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.exit_seq();
+        Ok(())
+    }
+}
+
+impl<'a, S: Sink> SerializeTuple for &'a mut IndexedSerializer<S> {
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize,
+    {
+        self.serialize_indexed_element(value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.exit_seq();
         Ok(())
     }
 }
+
+/// Serializes `value` to a `String`, using `delimiter` to separate fields, `map_like` to decide
+/// whether field names are emitted alongside their values, and `encoding` to decide how byte
+/// fields are represented.
+pub fn to_string<T: Serialize + ?Sized>(
+    value: &T, delimiter: &'static str, map_like: bool, encoding: BytesEncoding,
+) -> Result<String, Error> {
+    let mut serializer = SerializerConfig::new(delimiter)
+        .map_like(map_like)
+        .bytes_encoding(encoding)
+        .capacity(mem::size_of_val(value))
+        .build();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.finish())
+}
+
+/// Serializes `value` to a `Vec<u8>`, using `delimiter` to separate fields, `map_like` to decide
+/// whether field names are emitted alongside their values, and `encoding` to decide how byte
+/// fields are represented.
+///
+/// The returned buffer is pre-sized using `std::mem::size_of_val(value)` as a starting guess, so
+/// large level payloads don't repeatedly reallocate while being built up.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T, delimiter: &'static str, map_like: bool, encoding: BytesEncoding) -> Result<Vec<u8>, Error> {
+    let mut serializer = SerializerConfig::new(delimiter)
+        .map_like(map_like)
+        .bytes_encoding(encoding)
+        .capacity(mem::size_of_val(value))
+        .build();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_vec())
+}
+
+/// Serializes `value` directly into `writer`, using `delimiter` to separate fields, `map_like` to
+/// decide whether field names are emitted alongside their values, and `encoding` to decide how
+/// byte fields are represented.
+///
+/// Unlike [`to_string`]/[`to_vec`], this never buffers the whole output in memory, which makes it
+/// a good fit for serializing straight into a gzip encoder or a socket.
+pub fn to_writer<T: Serialize + ?Sized, W: io::Write>(
+    value: &T, delimiter: &'static str, map_like: bool, encoding: BytesEncoding, writer: W,
+) -> Result<(), Error> {
+    let mut serializer = SerializerConfig::new(delimiter).map_like(map_like).bytes_encoding(encoding).build_writer(writer);
+    value.serialize(&mut serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A byte field that serializes via `serialize_bytes` rather than as a sequence of `u8`s, the
+    /// way `Vec<u8>`'s own `Serialize` impl would.
+    struct Bytes<'a>(&'a [u8]);
+
+    impl<'a> Serialize for Bytes<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    /// A struct exercising the three field shapes (`nested seq + bytes + bool`) that matter most
+    /// for checking a sink implementation behaves the same as the others.
+    struct RepresentativeValue {
+        numbers: Vec<Vec<i32>>,
+        data: Vec<u8>,
+        flag: bool,
+    }
+
+    impl Serialize for RepresentativeValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("RepresentativeValue", 3)?;
+            s.serialize_field("numbers", &self.numbers)?;
+            s.serialize_field("data", &Bytes(&self.data))?;
+            s.serialize_field("flag", &self.flag)?;
+            s.end()
+        }
+    }
+
+    #[test]
+    fn to_writer_matches_to_string_and_to_vec() {
+        let value = RepresentativeValue {
+            numbers: vec![vec![1, 2], vec![3]],
+            data: vec![1, 2, 3],
+            flag: true,
+        };
+
+        let string = to_string(&value, ":", true, BytesEncoding::Base64UrlSafe).unwrap();
+        let vec = to_vec(&value, ":", true, BytesEncoding::Base64UrlSafe).unwrap();
+
+        let mut written = Vec::new();
+        to_writer(&value, ":", true, BytesEncoding::Base64UrlSafe, &mut written).unwrap();
+
+        assert_eq!(written, vec);
+        assert_eq!(written, string.into_bytes());
+    }
+
+    #[test]
+    fn nested_sequences_use_delimiters_by_depth() {
+        let nested: Vec<Vec<i32>> = vec![vec![1, 2], vec![3, 4]];
+
+        assert_eq!(to_string(&nested, ":", true, BytesEncoding::Base64UrlSafe).unwrap(), "1,2|3,4");
+    }
+
+    #[test]
+    fn none_in_first_position_emits_delimiter_without_starting() {
+        let values: Vec<Option<i32>> = vec![None, Some(5)];
+
+        assert_eq!(to_string(&values, ":", true, BytesEncoding::Base64UrlSafe).unwrap(), "|5");
+    }
+
+    #[test]
+    fn xor_base64_round_trips_a_known_vector() {
+        let mut serializer = SerializerConfig::new(":").bytes_encoding(BytesEncoding::XorBase64 { key: b"key" }).build();
+
+        Serializer::serialize_bytes(&mut serializer, b"hello").unwrap();
+
+        // b"hello" XOR-cycled with b"key" is [3, 0, 21, 7, 10], which base64-encodes (URL-safe) to
+        // this string.
+        assert_eq!(serializer.finish(), "AwAVBwo=");
+    }
+
+    #[test]
+    fn xor_base64_with_an_empty_key_errors_instead_of_panicking() {
+        let mut serializer = SerializerConfig::new(":").bytes_encoding(BytesEncoding::XorBase64 { key: b"" }).build();
+
+        assert!(Serializer::serialize_bytes(&mut serializer, b"hello").is_err());
+    }
+
+    #[test]
+    fn base64_standard_uses_the_standard_alphabet_with_padding() {
+        let mut serializer = SerializerConfig::new(":").bytes_encoding(BytesEncoding::Base64Standard).build();
+
+        Serializer::serialize_bytes(&mut serializer, &[0xfb, 0xff, 0xbf, 0xff]).unwrap();
+
+        assert_eq!(serializer.finish(), "+/+//w==");
+    }
+
+    #[test]
+    fn base64_url_safe_no_pad_uses_the_url_safe_alphabet_without_padding() {
+        let mut serializer = SerializerConfig::new(":").bytes_encoding(BytesEncoding::Base64UrlSafeNoPad).build();
+
+        Serializer::serialize_bytes(&mut serializer, &[0xfb, 0xff, 0xbf, 0xff]).unwrap();
+
+        assert_eq!(serializer.finish(), "-_-__w");
+    }
+
+    #[test]
+    fn bool_representation_empty_vs_present_hides_false() {
+        let mut false_serializer = SerializerConfig::new(":").bool_representation(BoolRepresentation::EmptyVsPresent).build();
+        Serializer::serialize_bool(&mut false_serializer, false).unwrap();
+        assert_eq!(false_serializer.finish(), "");
+
+        let mut true_serializer = SerializerConfig::new(":").bool_representation(BoolRepresentation::EmptyVsPresent).build();
+        Serializer::serialize_bool(&mut true_serializer, true).unwrap();
+        assert_eq!(true_serializer.finish(), "1");
+    }
+
+    #[test]
+    fn trailing_delimiter_applies_to_struct_end_but_not_seq_end() {
+        let mut serializer = SerializerConfig::new(":").trailing_delimiter(true).build();
+        let mut r#struct = Serializer::serialize_struct(&mut serializer, "Test", 1).unwrap();
+        SerializeStruct::serialize_field(&mut r#struct, "a", &1i32).unwrap();
+        SerializeStruct::end(r#struct).unwrap();
+        assert_eq!(serializer.finish(), "a:1:");
+
+        let mut seq_serializer = SerializerConfig::new(":").trailing_delimiter(true).build();
+        let mut seq = Serializer::serialize_seq(&mut seq_serializer, Some(2)).unwrap();
+        SerializeSeq::serialize_element(&mut seq, &1i32).unwrap();
+        SerializeSeq::serialize_element(&mut seq, &2i32).unwrap();
+        SerializeSeq::end(seq).unwrap();
+        assert_eq!(seq_serializer.finish(), "1|2");
+    }
+
+    #[test]
+    fn nesting_deeper_than_available_delimiters_reuses_the_innermost_one() {
+        // Four levels deep: the outer three consume `|`, `,` and `_`, and the fourth level has to
+        // fall back to reusing `_` rather than indexing past the end of `NESTED_DELIMITERS`.
+        let nested: Vec<Vec<Vec<Vec<i32>>>> = vec![vec![vec![vec![1, 2], vec![3, 4]]]];
+
+        assert_eq!(to_string(&nested, ":", true, BytesEncoding::Base64UrlSafe).unwrap(), "1_2_3_4");
+    }
+}